@@ -1,45 +1,524 @@
+use crate::disk_cache::DiskCache;
 use anyhow::{Context, Result};
-use regex::Regex;
-use scraper::{Html, Selector};
+use rand::Rng;
+use ratatui::style::Color;
+use scraper::node::Element;
+use scraper::{Html, Node, Selector};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// The seven classic teletext colors plus black, expressed as the closest
+/// RGB value we expect to see in RAI's inline `style`/`class` markup.
+const TELETEXT_PALETTE: [(u8, u8, u8, Color); 8] = [
+    (0x00, 0x00, 0x00, Color::Black),
+    (0xff, 0x00, 0x00, Color::Red),
+    (0x00, 0xff, 0x00, Color::Green),
+    (0xff, 0xff, 0x00, Color::Yellow),
+    (0x00, 0x00, 0xff, Color::Blue),
+    (0xff, 0x00, 0xff, Color::Magenta),
+    (0x00, 0xff, 0xff, Color::Cyan),
+    (0xff, 0xff, 0xff, Color::White),
+];
+
+/// One run of text with a single foreground/background color, the unit a
+/// `TelevideoPage` line is built from.
+///
+/// Serializing `fg`/`bg` requires ratatui's `serde` Cargo feature (it's what
+/// gives `ratatui::style::Color` a `Serialize`/`Deserialize` impl); it must
+/// stay enabled for `disk_cache`'s on-disk page cache to compile.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StyledSpan {
+    pub text: String,
+    pub fg: Color,
+    pub bg: Color,
+}
 
-#[derive(Clone)]
+/// Reads a `key: value` declaration out of an inline `style="..."` attribute,
+/// e.g. `extract_css_declaration("color:#ff0000", "color")` -> `Some("#ff0000")`.
+fn extract_css_declaration<'a>(style: &'a str, property: &str) -> Option<&'a str> {
+    style.split(';').find_map(|decl| {
+        let mut parts = decl.splitn(2, ':');
+        let key = parts.next()?.trim();
+        let value = parts.next()?.trim();
+        key.eq_ignore_ascii_case(property).then_some(value)
+    })
+}
+
+fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().trim_start_matches('#');
+    let expand = |c: char| u8::from_str_radix(&c.to_string().repeat(2), 16).ok();
+    match hex.len() {
+        6 => Some((
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        )),
+        3 => {
+            let mut chars = hex.chars();
+            Some((
+                expand(chars.next()?)?,
+                expand(chars.next()?)?,
+                expand(chars.next()?)?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn nearest_teletext_color(rgb: (u8, u8, u8)) -> Color {
+    TELETEXT_PALETTE
+        .iter()
+        .min_by_key(|(r, g, b, _)| {
+            let dr = *r as i32 - rgb.0 as i32;
+            let dg = *g as i32 - rgb.1 as i32;
+            let db = *b as i32 - rgb.2 as i32;
+            dr * dr + dg * dg + db * db
+        })
+        .map(|(_, _, _, color)| *color)
+        .unwrap_or(Color::White)
+}
+
+/// Maps a teletext CSS class name (RAI's markup uses short color-name
+/// classes such as `"red"` or `"giallo"`) to a palette color.
+fn teletext_color_from_class(class: &str) -> Option<Color> {
+    let class = class.to_ascii_lowercase();
+    match class.as_str() {
+        "black" | "nero" => Some(Color::Black),
+        "red" | "rosso" => Some(Color::Red),
+        "green" | "verde" => Some(Color::Green),
+        "yellow" | "giallo" => Some(Color::Yellow),
+        "blue" | "blu" | "azzurro" => Some(Color::Blue),
+        "magenta" | "viola" => Some(Color::Magenta),
+        "cyan" | "ciano" => Some(Color::Cyan),
+        "white" | "bianco" => Some(Color::White),
+        _ => None,
+    }
+}
+
+/// Resolves the effective foreground/background color for `elem`, inheriting
+/// from the parent's `(fg, bg)` unless its own `style`/`class` override them.
+fn resolve_colors(elem: &Element, fg: Color, bg: Color) -> (Color, Color) {
+    let mut new_fg = fg;
+    let mut new_bg = bg;
+
+    if let Some(style) = elem.attr("style") {
+        if let Some(hex) = extract_css_declaration(style, "color").and_then(parse_hex_color) {
+            new_fg = nearest_teletext_color(hex);
+        }
+        if let Some(hex) = extract_css_declaration(style, "background-color").and_then(parse_hex_color) {
+            new_bg = nearest_teletext_color(hex);
+        }
+    }
+
+    if let Some(class) = elem.attr("class") {
+        for token in class.split_whitespace() {
+            if let Some(color) = teletext_color_from_class(token) {
+                new_fg = color;
+            }
+        }
+    }
+
+    (new_fg, new_bg)
+}
+
+/// Appends `text` to `lines`, starting a new line for each `\n` it contains.
+fn push_styled_text(text: &str, fg: Color, bg: Color, lines: &mut Vec<Vec<StyledSpan>>) {
+    let mut parts = text.split('\n');
+    if let Some(first) = parts.next() {
+        if !first.is_empty() {
+            lines.last_mut().unwrap().push(StyledSpan {
+                text: first.to_string(),
+                fg,
+                bg,
+            });
+        }
+    }
+    for part in parts {
+        lines.push(Vec::new());
+        if !part.is_empty() {
+            lines.last_mut().unwrap().push(StyledSpan {
+                text: part.to_string(),
+                fg,
+                bg,
+            });
+        }
+    }
+}
+
+/// Walks the `<pre>` subtree depth-first, threading the inherited color down
+/// to each text node and splitting on newlines into `lines`.
+fn walk_node(node: ego_tree::NodeRef<Node>, fg: Color, bg: Color, lines: &mut Vec<Vec<StyledSpan>>) {
+    match node.value() {
+        Node::Text(text) => push_styled_text(text, fg, bg, lines),
+        Node::Element(elem) => {
+            let (fg, bg) = resolve_colors(elem, fg, bg);
+            for child in node.children() {
+                walk_node(child, fg, bg, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Delay before the first retry attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Per-attempt delay is multiplied by this factor after each failure.
+const RETRY_BACKOFF_FACTOR: f64 = 2.0;
+/// No single retry ever waits longer than this.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(8);
+/// Give up once this many attempts have been made...
+const MAX_RETRY_ATTEMPTS: u32 = 5;
+/// ...or once this much wall-clock time has elapsed, whichever comes first.
+const MAX_RETRY_ELAPSED: Duration = Duration::from_secs(30);
+
+/// Default TCP connect timeout for the shared HTTP client.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default end-to-end request timeout; bounds how long a hung RAI server
+/// can freeze the UI thread.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const USER_AGENT: &str = concat!("televideo-term/", env!("CARGO_PKG_VERSION"));
+
+/// Builds the shared `reqwest` client, picking the TLS backend selected at
+/// compile time via the `default-tls` / `rustls-tls` Cargo features.
+#[cfg(feature = "rustls-tls")]
+fn build_http_client(connect_timeout: Duration, request_timeout: Duration) -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .use_rustls_tls()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+#[cfg(not(feature = "rustls-tls"))]
+fn build_http_client(connect_timeout: Duration, request_timeout: Duration) -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+/// The outcome of a single fetch attempt: transient failures (dropped
+/// connections, 5xx, rate limiting) are worth retrying, permanent ones
+/// (404 on a page that simply doesn't exist) are not.
+enum FetchError {
+    Transient(anyhow::Error),
+    Permanent(anyhow::Error),
+}
+
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        429 | 500 | 502 | 503 | 504
+    )
+}
+
+/// Runs `attempt_fn` with exponential backoff and jitter until it succeeds,
+/// fails permanently, or the retry budget (attempts or elapsed time) is
+/// exhausted. `on_attempt(attempt, max_attempts)` is called before each
+/// retry sleep so callers can surface progress (e.g. "Retrying (2/5)...").
+/// Returns the original [`FetchError`] rather than collapsing it, so callers
+/// that care whether the final failure was permanent (e.g. to record a
+/// discovered sub-page boundary) can still tell.
+fn retry_with_backoff<T>(
+    mut attempt_fn: impl FnMut() -> Result<T, FetchError>,
+    mut on_attempt: impl FnMut(u32, u32),
+) -> Result<T, FetchError> {
+    let start = Instant::now();
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 0u32;
+
+    loop {
+        attempt += 1;
+        match attempt_fn() {
+            Ok(value) => return Ok(value),
+            Err(FetchError::Permanent(e)) => return Err(FetchError::Permanent(e)),
+            Err(FetchError::Transient(e)) => {
+                if attempt >= MAX_RETRY_ATTEMPTS || start.elapsed() >= MAX_RETRY_ELAPSED {
+                    return Err(FetchError::Transient(e));
+                }
+
+                on_attempt(attempt, MAX_RETRY_ATTEMPTS);
+
+                let jitter = rand::thread_rng().gen_range(0.5..1.5);
+                let sleep_for = delay.mul_f64(jitter).min(MAX_RETRY_DELAY);
+                thread::sleep(sleep_for);
+
+                delay = delay.mul_f64(RETRY_BACKOFF_FACTOR).min(MAX_RETRY_DELAY);
+            }
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[allow(dead_code)]
 pub struct TelevideoPage {
     pub page_number: u16,
     pub sub_page: u16,
-    pub lines: Vec<String>,
+    pub lines: Vec<Vec<StyledSpan>>,
     pub timestamp: String,
 }
 
+type PageCache = Arc<Mutex<HashMap<(u16, u16), (TelevideoPage, SystemTime)>>>;
+type ImageCache = Arc<Mutex<HashMap<(u16, u16), (image::DynamicImage, SystemTime)>>>;
+
+/// Sent back over `TelevideoClient`'s prefetch channel once a background
+/// prefetch finishes, purely so `run_app`'s poll loop can drain the channel.
+/// The actual fetched data is already in the shared caches by then.
+pub struct PrefetchDone {
+    pub page: u16,
+    pub sub_page: u16,
+}
+
+/// How many prefetches are allowed to run at once. Kept small since each one
+/// can block a worker thread for longer than [`MAX_RETRY_ELAPSED`] on a dead
+/// host: it runs a page fetch and then an image fetch, each with its own
+/// retry budget.
+const PREFETCH_WORKER_COUNT: usize = 2;
+
+type PrefetchJob = Box<dyn FnOnce() + Send>;
+
+/// A tiny fixed-size pool that runs queued prefetch jobs, so rapid
+/// navigation queues up work instead of spawning an unbounded number of
+/// blocking OS threads.
+struct PrefetchPool {
+    tx: mpsc::Sender<PrefetchJob>,
+}
+
+impl PrefetchPool {
+    fn new(worker_count: usize) -> Self {
+        let (tx, rx) = mpsc::channel::<PrefetchJob>();
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..worker_count {
+            let rx = Arc::clone(&rx);
+            thread::spawn(move || loop {
+                let job = rx.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => break,
+                }
+            });
+        }
+        Self { tx }
+    }
+
+    fn spawn(&self, job: PrefetchJob) {
+        let _ = self.tx.send(job);
+    }
+}
+
+#[derive(Clone)]
 pub struct TelevideoClient {
-    cache: HashMap<(u16, u16), (TelevideoPage, SystemTime)>,
-    image_cache: HashMap<(u16, u16), (image::DynamicImage, SystemTime)>,
+    cache: PageCache,
+    image_cache: ImageCache,
     base_url: String,
     image_base_url: String,
+    cache_ttl: Duration,
+    client: reqwest::blocking::Client,
+    disk_cache: DiskCache,
+    offline: bool,
+    /// Age of the stale/offline disk copy served by the most recent fetch,
+    /// if the fetch didn't come back fresh from memory/network. Cleared by
+    /// [`take_staleness`](Self::take_staleness).
+    last_staleness: Option<Duration>,
+    /// Highest sub-page known to exist for a given page, discovered the
+    /// first time a higher sub-page comes back not-found. Shared so both
+    /// auto-rotate and prefetch can stop probing past it.
+    known_subpages: Arc<Mutex<HashMap<u16, u16>>>,
+    /// Bumped every time a page is loaded (see `App::load_page`), so in-flight
+    /// prefetches for the old neighbourhood can recognize themselves as
+    /// stale and skip reporting back.
+    prefetch_epoch: Arc<AtomicU64>,
+    prefetch_tx: mpsc::Sender<PrefetchDone>,
+    prefetch_rx: Arc<Mutex<mpsc::Receiver<PrefetchDone>>>,
+    prefetch_pool: Arc<PrefetchPool>,
 }
 
 impl TelevideoClient {
     pub fn new() -> Self {
+        Self::with_config(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Builds a client with explicit connect/request timeouts, reusing the
+    /// hardcoded `base_url`/`image_base_url`/cache TTL defaults.
+    pub fn with_config(connect_timeout: Duration, request_timeout: Duration) -> Self {
+        let (prefetch_tx, prefetch_rx) = mpsc::channel();
+        Self {
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            image_cache: Arc::new(Mutex::new(HashMap::new())),
+            base_url: crate::config::DEFAULT_TEXT_BASE_URL.to_string(),
+            image_base_url: crate::config::DEFAULT_IMAGE_BASE_URL.to_string(),
+            cache_ttl: Duration::from_secs(crate::config::DEFAULT_CACHE_TTL_SECONDS),
+            client: build_http_client(connect_timeout, request_timeout),
+            disk_cache: DiskCache::new(),
+            offline: false,
+            last_staleness: None,
+            known_subpages: Arc::new(Mutex::new(HashMap::new())),
+            prefetch_epoch: Arc::new(AtomicU64::new(0)),
+            prefetch_tx,
+            prefetch_rx: Arc::new(Mutex::new(prefetch_rx)),
+            prefetch_pool: Arc::new(PrefetchPool::new(PREFETCH_WORKER_COUNT)),
+        }
+    }
+
+    /// Builds a client from a loaded [`Config`](crate::config::Config).
+    /// Connect/request timeouts aren't user-configurable yet, so this keeps
+    /// using the same defaults as [`new`](Self::new).
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let (prefetch_tx, prefetch_rx) = mpsc::channel();
         Self {
-            cache: HashMap::new(),
-            image_cache: HashMap::new(),
-            base_url: "https://www.servizitelevideo.rai.it/televideo/pub/solotesto.jsp".to_string(),
-            image_base_url: "http://www.televideo.rai.it/televideo/pub/tt4web/Nazionale".to_string(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            image_cache: Arc::new(Mutex::new(HashMap::new())),
+            base_url: config.text_base_url.clone(),
+            image_base_url: config.image_base_url.clone(),
+            cache_ttl: Duration::from_secs(config.cache_ttl_seconds),
+            client: build_http_client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT),
+            disk_cache: DiskCache::new(),
+            offline: false,
+            last_staleness: None,
+            known_subpages: Arc::new(Mutex::new(HashMap::new())),
+            prefetch_epoch: Arc::new(AtomicU64::new(0)),
+            prefetch_tx,
+            prefetch_rx: Arc::new(Mutex::new(prefetch_rx)),
+            prefetch_pool: Arc::new(PrefetchPool::new(PREFETCH_WORKER_COUNT)),
+        }
+    }
+
+    /// Remembers that `page` has exactly `max_sub_page` sub-pages, discovered
+    /// from sub-page `max_sub_page + 1` coming back not-found.
+    fn record_max_subpage(&self, page: u16, max_sub_page: u16) {
+        self.known_subpages.lock().unwrap().insert(page, max_sub_page);
+    }
+
+    /// The highest sub-page known to exist for `page`, if discovered yet.
+    /// `None` means no sub-page of it has come back not-found yet.
+    pub fn known_subpage_count(&self, page: u16) -> Option<u16> {
+        self.known_subpages.lock().unwrap().get(&page).copied()
+    }
+
+    /// Spawns background workers that speculatively fetch the pages
+    /// adjacent to `(page, sub_page)` (`page-1`, `page+1`, `sub_page+1`)
+    /// into the shared caches, so the likely next navigation is instant.
+    pub fn prefetch_neighbors(&self, page: u16, sub_page: u16) {
+        let mut targets = vec![(page.wrapping_sub(1), 1), (page.wrapping_add(1), 1)];
+
+        // Only bother prefetching the next sub-page if one might exist:
+        // either we haven't discovered the boundary yet, or we have and
+        // we're not at it (a known single-sub-page page is never probed).
+        let worth_probing_next_subpage = match self.known_subpage_count(page) {
+            Some(max) => sub_page < max,
+            None => true,
+        };
+        if worth_probing_next_subpage {
+            targets.push((page, sub_page + 1));
+        }
+
+        for (target_page, target_sub_page) in targets {
+            if !(100..=899).contains(&target_page) {
+                continue;
+            }
+            self.prefetch_one(target_page, target_sub_page);
         }
     }
 
+    fn prefetch_one(&self, page: u16, sub_page: u16) {
+        let expected_epoch = self.prefetch_epoch.load(Ordering::Relaxed);
+        let epoch = Arc::clone(&self.prefetch_epoch);
+        let tx = self.prefetch_tx.clone();
+        let mut worker = self.clone();
+        let is_stale = move || epoch.load(Ordering::Relaxed) != expected_epoch;
+
+        self.prefetch_pool.spawn(Box::new(move || {
+            // Bail before doing any work if the user already moved on; a
+            // busy pool can leave this queued long enough to go stale.
+            if is_stale() {
+                return;
+            }
+            let _ = worker.fetch_page(page, sub_page);
+
+            if is_stale() {
+                return;
+            }
+            let _ = worker.fetch_image(page, sub_page);
+
+            if !is_stale() {
+                let _ = tx.send(PrefetchDone { page, sub_page });
+            }
+        }));
+    }
+
+    /// Drains completed prefetch notifications; stale ones (their epoch no
+    /// longer matches because the user jumped elsewhere) were already
+    /// filtered out by the worker before it sent them.
+    pub fn drain_prefetch_results(&self) -> Vec<PrefetchDone> {
+        let rx = self.prefetch_rx.lock().unwrap();
+        rx.try_iter().collect()
+    }
+
+    /// Invalidates in-flight prefetches for the page the user just left,
+    /// e.g. after an explicit jump via the page-input buffer.
+    pub fn cancel_pending_prefetches(&self) {
+        self.prefetch_epoch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// When `true`, fetches never touch the network and are served only
+    /// from the in-memory/disk caches, failing if neither has the page.
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Returns and clears the age of the stale/offline copy served by the
+    /// most recent `fetch_page`/`fetch_image` call, if any.
+    pub fn take_staleness(&mut self) -> Option<Duration> {
+        self.last_staleness.take()
+    }
+
     pub fn fetch_page(&mut self, page: u16, sub_page: u16) -> Result<TelevideoPage> {
+        self.fetch_page_with_progress(page, sub_page, |_, _| {})
+    }
+
+    /// Same as [`fetch_page`](Self::fetch_page), but calls `on_attempt(attempt,
+    /// max_attempts)` before each retry sleep so the caller can report progress.
+    pub fn fetch_page_with_progress(
+        &mut self,
+        page: u16,
+        sub_page: u16,
+        on_attempt: impl FnMut(u32, u32),
+    ) -> Result<TelevideoPage> {
         let cache_key = (page, sub_page);
+        self.last_staleness = None;
 
-        // Check cache (5 minute expiry)
-        if let Some((data, time)) = self.cache.get(&cache_key) {
-            if time.elapsed().unwrap_or(Duration::from_secs(301)) < Duration::from_secs(300) {
+        // Check memory cache (configurable expiry)
+        if let Some((data, time)) = self.cache.lock().unwrap().get(&cache_key) {
+            if time.elapsed().unwrap_or(self.cache_ttl + Duration::from_secs(1)) < self.cache_ttl {
                 return Ok(data.clone());
             }
         }
 
+        // Fall back to the disk cache before hitting the network
+        if let Some((data, fetched_at)) = self.disk_cache.load_page(page, sub_page) {
+            if fetched_at.elapsed().unwrap_or(self.cache_ttl + Duration::from_secs(1)) < self.cache_ttl {
+                self.cache.lock().unwrap().insert(cache_key, (data.clone(), fetched_at));
+                return Ok(data);
+            }
+            if self.offline {
+                self.last_staleness = fetched_at.elapsed().ok();
+                return Ok(data);
+            }
+        } else if self.offline {
+            anyhow::bail!("Page {}.{} not available offline", page, sub_page);
+        }
+
         // Build URL for solotesto.jsp
         let url = if sub_page > 1 {
             format!("{}?pagina={}&sottopagina={}", self.base_url, page, sub_page)
@@ -47,24 +526,64 @@ impl TelevideoClient {
             format!("{}?pagina={}", self.base_url, page)
         };
 
-        // Fetch the HTML
-        let response = reqwest::blocking::get(&url)
-            .context("Failed to fetch page")?;
+        let client = &self.client;
+        let fetch_result = match retry_with_backoff(|| Self::fetch_page_once(client, &url, page, sub_page), on_attempt) {
+            Ok(html) => self.parse_html(&html, page, sub_page),
+            Err(FetchError::Permanent(e)) => {
+                // A permanent failure on a sub-page request means it simply
+                // doesn't exist; remember that boundary for auto-rotate and
+                // prefetch instead of re-probing it forever.
+                if sub_page > 1 {
+                    self.record_max_subpage(page, sub_page - 1);
+                }
+                Err(e)
+            }
+            Err(FetchError::Transient(e)) => Err(e),
+        };
 
-        if !response.status().is_success() {
-            anyhow::bail!("Page {}.{} not found", page, sub_page);
+        match fetch_result {
+            Ok(televideo_page) => {
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .insert(cache_key, (televideo_page.clone(), SystemTime::now()));
+                self.disk_cache.store_page(page, sub_page, &televideo_page);
+                Ok(televideo_page)
+            }
+            Err(e) => match self.disk_cache.load_page(page, sub_page) {
+                Some((data, fetched_at)) => {
+                    self.last_staleness = fetched_at.elapsed().ok();
+                    Ok(data)
+                }
+                None => Err(e),
+            },
         }
+    }
 
-        let html = response.text()
-            .context("Failed to read response")?;
-
-        // Parse the HTML
-        let televideo_page = self.parse_html(&html, page, sub_page)?;
-
-        // Cache it
-        self.cache.insert(cache_key, (televideo_page.clone(), SystemTime::now()));
+    fn fetch_page_once(
+        client: &reqwest::blocking::Client,
+        url: &str,
+        page: u16,
+        sub_page: u16,
+    ) -> Result<String, FetchError> {
+        let response = client
+            .get(url)
+            .send()
+            .map_err(|e| FetchError::Transient(anyhow::Error::new(e).context("Failed to fetch page")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let err = anyhow::anyhow!("Page {}.{} not found (status {})", page, sub_page, status);
+            return if is_transient_status(status) {
+                Err(FetchError::Transient(err))
+            } else {
+                Err(FetchError::Permanent(err))
+            };
+        }
 
-        Ok(televideo_page)
+        response
+            .text()
+            .map_err(|e| FetchError::Transient(anyhow::Error::new(e).context("Failed to read response")))
     }
 
     fn parse_html(&self, html: &str, page: u16, sub_page: u16) -> Result<TelevideoPage> {
@@ -85,31 +604,29 @@ impl TelevideoClient {
 
         let document = Html::parse_fragment(content_section);
 
-        // Find the <pre> tag which contains the formatted content
+        // Find the <pre> tag which contains the formatted, colored content
         let pre_selector = Selector::parse("pre").unwrap();
 
-        let mut lines = Vec::new();
+        let mut lines: Vec<Vec<StyledSpan>> = vec![Vec::new()];
 
         if let Some(pre_element) = document.select(&pre_selector).next() {
-            // Get the HTML content to process links
-            let pre_html = pre_element.html();
-
-            // Replace <a> tags with just their text content
-            let link_regex = Regex::new(r#"<a href="[^"]*">([^<]+)</a>"#).unwrap();
-            let cleaned = link_regex.replace_all(&pre_html, "$1");
-
-            // Parse as HTML to get text with preserved whitespace
-            let clean_doc = Html::parse_fragment(&cleaned);
-            let text_content = clean_doc.root_element().text().collect::<String>();
-
-            // Split into lines and preserve them
-            for line in text_content.lines() {
-                lines.push(line.to_string());
+            let (fg, bg) = resolve_colors(pre_element.value(), Color::White, Color::Black);
+            for child in pre_element.children() {
+                walk_node(child, fg, bg, &mut lines);
             }
         }
 
-        if lines.is_empty() {
-            lines.push("(No content found on this page)".to_string());
+        // The trailing `\n` inside <pre> leaves one empty line at the end; drop it.
+        if lines.len() > 1 && lines.last().map(Vec::is_empty).unwrap_or(false) {
+            lines.pop();
+        }
+
+        if lines.iter().all(Vec::is_empty) {
+            lines = vec![vec![StyledSpan {
+                text: "(No content found on this page)".to_string(),
+                fg: Color::White,
+                bg: Color::Black,
+            }]];
         }
 
         Ok(TelevideoPage {
@@ -121,15 +638,47 @@ impl TelevideoClient {
     }
 
     pub fn fetch_image(&mut self, page: u16, sub_page: u16) -> Result<image::DynamicImage> {
+        self.fetch_image_with_progress(page, sub_page, |_, _| {})
+    }
+
+    /// Same as [`fetch_image`](Self::fetch_image), but calls `on_attempt(attempt,
+    /// max_attempts)` before each retry sleep so the caller can report progress.
+    pub fn fetch_image_with_progress(
+        &mut self,
+        page: u16,
+        sub_page: u16,
+        on_attempt: impl FnMut(u32, u32),
+    ) -> Result<image::DynamicImage> {
         let cache_key = (page, sub_page);
+        self.last_staleness = None;
 
-        // Check cache (5 minute expiry)
-        if let Some((img, time)) = self.image_cache.get(&cache_key) {
-            if time.elapsed().unwrap_or(Duration::from_secs(301)) < Duration::from_secs(300) {
+        // Check memory cache (configurable expiry)
+        if let Some((img, time)) = self.image_cache.lock().unwrap().get(&cache_key) {
+            if time.elapsed().unwrap_or(self.cache_ttl + Duration::from_secs(1)) < self.cache_ttl {
                 return Ok(img.clone());
             }
         }
 
+        // Fall back to the disk cache before hitting the network
+        if let Some((bytes, fetched_at)) = self.disk_cache.load_image(page, sub_page) {
+            if fetched_at.elapsed().unwrap_or(self.cache_ttl + Duration::from_secs(1)) < self.cache_ttl {
+                if let Ok(img) = image::load_from_memory(&bytes) {
+                    self.image_cache
+                        .lock()
+                        .unwrap()
+                        .insert(cache_key, (img.clone(), fetched_at));
+                    return Ok(img);
+                }
+            } else if self.offline {
+                let img = image::load_from_memory(&bytes).context("Failed to decode cached image")?;
+                self.last_staleness = fetched_at.elapsed().ok();
+                return Ok(img);
+            }
+        }
+        if self.offline {
+            anyhow::bail!("Image for page {}.{} not available offline", page, sub_page);
+        }
+
         // Build URL - use 16:9 widescreen version for better quality
         let url = if sub_page > 1 {
             format!("{}/16_9_page-{}.{}.png", self.image_base_url, page, sub_page)
@@ -137,29 +686,111 @@ impl TelevideoClient {
             format!("{}/16_9_page-{}.png", self.image_base_url, page)
         };
 
-        // Fetch the image
-        let response = reqwest::blocking::get(&url)
-            .context("Failed to fetch image")?;
+        let client = &self.client;
+        let fetch_result = retry_with_backoff(|| Self::fetch_image_once(client, &url, page, sub_page), on_attempt)
+            .map_err(|e| match e {
+                FetchError::Permanent(e) | FetchError::Transient(e) => e,
+            });
+
+        match fetch_result {
+            Ok(bytes) => {
+                let img = image::load_from_memory(&bytes).context("Failed to decode image")?;
+                self.image_cache
+                    .lock()
+                    .unwrap()
+                    .insert(cache_key, (img.clone(), SystemTime::now()));
+                self.disk_cache.store_image(page, sub_page, &bytes);
+                Ok(img)
+            }
+            Err(e) => match self.disk_cache.load_image(page, sub_page) {
+                Some((bytes, fetched_at)) => {
+                    let img = image::load_from_memory(&bytes).context("Failed to decode cached image")?;
+                    self.last_staleness = fetched_at.elapsed().ok();
+                    Ok(img)
+                }
+                None => Err(e),
+            },
+        }
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!("Image for page {}.{} not found", page, sub_page);
+    fn fetch_image_once(
+        client: &reqwest::blocking::Client,
+        url: &str,
+        page: u16,
+        sub_page: u16,
+    ) -> Result<bytes::Bytes, FetchError> {
+        let response = client
+            .get(url)
+            .send()
+            .map_err(|e| FetchError::Transient(anyhow::Error::new(e).context("Failed to fetch image")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let err = anyhow::anyhow!("Image for page {}.{} not found (status {})", page, sub_page, status);
+            return if is_transient_status(status) {
+                Err(FetchError::Transient(err))
+            } else {
+                Err(FetchError::Permanent(err))
+            };
         }
 
-        let bytes = response.bytes()
-            .context("Failed to read image response")?;
+        response
+            .bytes()
+            .map_err(|e| FetchError::Transient(anyhow::Error::new(e).context("Failed to read image response")))
+    }
 
-        // Load image from bytes
-        let img = image::load_from_memory(&bytes)
-            .context("Failed to decode image")?;
+    pub fn clear_cache(&mut self) {
+        self.cache.lock().unwrap().clear();
+        self.image_cache.lock().unwrap().clear();
+    }
+}
 
-        // Cache it
-        self.image_cache.insert(cache_key, (img.clone(), SystemTime::now()));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(img)
+    #[test]
+    fn retries_rate_limit_and_server_errors() {
+        for code in [429, 500, 502, 503, 504] {
+            let status = reqwest::StatusCode::from_u16(code).unwrap();
+            assert!(is_transient_status(status), "{} should be transient", code);
+        }
     }
 
-    pub fn clear_cache(&mut self) {
-        self.cache.clear();
-        self.image_cache.clear();
+    #[test]
+    fn does_not_retry_not_found_or_success() {
+        for code in [200, 301, 404, 410] {
+            let status = reqwest::StatusCode::from_u16(code).unwrap();
+            assert!(!is_transient_status(status), "{} should not be transient", code);
+        }
+    }
+
+    #[test]
+    fn extracts_css_declaration_case_insensitively() {
+        assert_eq!(
+            extract_css_declaration("color:#ff0000; background-color: #000", "color"),
+            Some("#ff0000")
+        );
+        assert_eq!(
+            extract_css_declaration("COLOR: #ff0000", "color"),
+            Some("#ff0000")
+        );
+        assert_eq!(extract_css_declaration("color:#ff0000", "background-color"), None);
+    }
+
+    #[test]
+    fn parses_six_and_three_digit_hex_colors() {
+        assert_eq!(parse_hex_color("#ff0000"), Some((0xff, 0x00, 0x00)));
+        assert_eq!(parse_hex_color("00ff00"), Some((0x00, 0xff, 0x00)));
+        assert_eq!(parse_hex_color("#f00"), Some((0xff, 0x00, 0x00)));
+        assert_eq!(parse_hex_color("#ff00"), None);
+        assert_eq!(parse_hex_color("not-a-color"), None);
+    }
+
+    #[test]
+    fn maps_rgb_to_nearest_teletext_color() {
+        assert_eq!(nearest_teletext_color((0xff, 0x00, 0x00)), Color::Red);
+        assert_eq!(nearest_teletext_color((0x00, 0x00, 0x00)), Color::Black);
+        assert_eq!(nearest_teletext_color((0xfe, 0x01, 0x01)), Color::Red);
     }
 }
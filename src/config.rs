@@ -0,0 +1,59 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub(crate) const DEFAULT_TEXT_BASE_URL: &str =
+    "https://www.servizitelevideo.rai.it/televideo/pub/solotesto.jsp";
+pub(crate) const DEFAULT_IMAGE_BASE_URL: &str =
+    "http://www.televideo.rai.it/televideo/pub/tt4web/Nazionale";
+pub(crate) const DEFAULT_CACHE_TTL_SECONDS: u64 = 300;
+const DEFAULT_START_PAGE: u16 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultDisplayMode {
+    Text,
+    Image,
+}
+
+/// User-facing configuration, loaded from `config.toml` in the platform
+/// config dir (e.g. `~/.config/televideo-term/config.toml` on Linux).
+/// Any field left out of the file keeps its hardcoded default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub text_base_url: String,
+    pub image_base_url: String,
+    pub cache_ttl_seconds: u64,
+    pub start_page: u16,
+    pub default_display_mode: DefaultDisplayMode,
+    pub favorites: HashMap<String, u16>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            text_base_url: DEFAULT_TEXT_BASE_URL.to_string(),
+            image_base_url: DEFAULT_IMAGE_BASE_URL.to_string(),
+            cache_ttl_seconds: DEFAULT_CACHE_TTL_SECONDS,
+            start_page: DEFAULT_START_PAGE,
+            default_display_mode: DefaultDisplayMode::Text,
+            favorites: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads `config.toml` from the platform config dir. Falls back to
+    /// [`Config::default`] if the file is missing or fails to parse.
+    pub fn load() -> Self {
+        match Self::config_path().and_then(|path| std::fs::read_to_string(path).ok()) {
+            Some(raw) => toml::from_str(&raw).unwrap_or_default(),
+            None => Self::default(),
+        }
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("televideo-term").join("config.toml"))
+    }
+}
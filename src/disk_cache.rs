@@ -0,0 +1,110 @@
+use crate::client::TelevideoPage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize)]
+struct CachedPage {
+    page: TelevideoPage,
+    fetched_at_unix: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedImageMeta {
+    fetched_at_unix: u64,
+}
+
+/// On-disk mirror of the in-memory page/image caches, so pages render
+/// instantly on repeat launches and stay available when the RAI servers
+/// aren't reachable. Every entry is keyed by `(page, sub_page)` and stamped
+/// with the time it was fetched.
+#[derive(Clone)]
+pub struct DiskCache {
+    dir: Option<PathBuf>,
+}
+
+impl DiskCache {
+    pub fn new() -> Self {
+        Self {
+            dir: dirs::cache_dir().map(|dir| dir.join("televideo-term")),
+        }
+    }
+
+    pub fn load_page(&self, page: u16, sub_page: u16) -> Option<(TelevideoPage, SystemTime)> {
+        let raw = fs::read(self.page_path(page, sub_page)?).ok()?;
+        let cached: CachedPage = serde_json::from_slice(&raw).ok()?;
+        Some((cached.page, unix_to_system_time(cached.fetched_at_unix)))
+    }
+
+    pub fn store_page(&self, page: u16, sub_page: u16, content: &TelevideoPage) {
+        let Some(path) = self.page_path(page, sub_page) else {
+            return;
+        };
+        if !self.ensure_dir() {
+            return;
+        }
+        let cached = CachedPage {
+            page: content.clone(),
+            fetched_at_unix: unix_now(),
+        };
+        if let Ok(json) = serde_json::to_vec(&cached) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    pub fn load_image(&self, page: u16, sub_page: u16) -> Option<(Vec<u8>, SystemTime)> {
+        let bytes = fs::read(self.image_path(page, sub_page)?).ok()?;
+        let meta_raw = fs::read(self.image_meta_path(page, sub_page)?).ok()?;
+        let meta: CachedImageMeta = serde_json::from_slice(&meta_raw).ok()?;
+        Some((bytes, unix_to_system_time(meta.fetched_at_unix)))
+    }
+
+    pub fn store_image(&self, page: u16, sub_page: u16, bytes: &[u8]) {
+        let (Some(image_path), Some(meta_path)) =
+            (self.image_path(page, sub_page), self.image_meta_path(page, sub_page))
+        else {
+            return;
+        };
+        if !self.ensure_dir() {
+            return;
+        }
+        let _ = fs::write(image_path, bytes);
+        let meta = CachedImageMeta {
+            fetched_at_unix: unix_now(),
+        };
+        if let Ok(json) = serde_json::to_vec(&meta) {
+            let _ = fs::write(meta_path, json);
+        }
+    }
+
+    fn ensure_dir(&self) -> bool {
+        match &self.dir {
+            Some(dir) => fs::create_dir_all(dir).is_ok(),
+            None => false,
+        }
+    }
+
+    fn page_path(&self, page: u16, sub_page: u16) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("page-{}-{}.json", page, sub_page)))
+    }
+
+    fn image_path(&self, page: u16, sub_page: u16) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("image-{}-{}.png", page, sub_page)))
+    }
+
+    fn image_meta_path(&self, page: u16, sub_page: u16) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("image-{}-{}.meta.json", page, sub_page)))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn unix_to_system_time(unix_secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(unix_secs)
+}
@@ -1,7 +1,10 @@
 mod client;
+mod config;
+mod disk_cache;
 
 use anyhow::Result;
 use client::{TelevideoClient, TelevideoPage};
+use config::Config;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
@@ -17,6 +20,11 @@ use ratatui::{
 };
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol, Resize, StatefulImage};
 use std::io;
+use std::time::{Duration, Instant};
+
+/// Interval between automatic sub-page advances when auto-rotate is on.
+/// Fixed at compile time; the config file has no setting for it.
+const AUTO_ROTATE_INTERVAL: Duration = Duration::from_secs(8);
 
 #[derive(PartialEq, Clone, Copy)]
 enum DisplayMode {
@@ -26,9 +34,11 @@ enum DisplayMode {
 
 struct App {
     client: TelevideoClient,
+    config: Config,
     current_page: u16,
     current_part: u16,
     page_input_buffer: String,
+    favorite_input_buffer: Option<String>,
     content: Option<TelevideoPage>,
     image_state: Option<StatefulProtocol>,
     error: Option<String>,
@@ -36,22 +46,35 @@ struct App {
     loading: bool,
     display_mode: DisplayMode,
     picker: Picker,
+    auto_rotate: bool,
+    last_rotate: Instant,
 }
 
 impl App {
-    fn new_with_picker(picker: Picker) -> Self {
+    fn new_with_picker(picker: Picker, config: Config, offline: bool) -> Self {
+        let start_page = config.start_page;
+        let display_mode = match config.default_display_mode {
+            config::DefaultDisplayMode::Text => DisplayMode::Text,
+            config::DefaultDisplayMode::Image => DisplayMode::Image,
+        };
+        let mut client = TelevideoClient::from_config(&config);
+        client.set_offline(offline);
         Self {
-            client: TelevideoClient::new(),
-            current_page: 100,
+            client,
+            config,
+            current_page: start_page,
             current_part: 1,
             page_input_buffer: String::new(),
+            favorite_input_buffer: None,
             content: None,
             image_state: None,
             error: None,
             message: None,
             loading: false,
-            display_mode: DisplayMode::Text,
+            display_mode,
             picker,
+            auto_rotate: false,
+            last_rotate: Instant::now(),
         }
     }
 
@@ -59,10 +82,12 @@ impl App {
         self.loading = true;
         self.error = None;
         self.message = None;
+        self.last_rotate = Instant::now();
 
-        // Load text content
-        let text_result = self.client.fetch_page(page, part);
-        match text_result {
+        // Fetches block this thread for the whole retry budget, so there's
+        // no draw loop running to paint per-attempt progress; just take the
+        // final result.
+        match self.client.fetch_page(page, part) {
             Ok(page_content) => {
                 self.content = Some(page_content);
             }
@@ -71,9 +96,7 @@ impl App {
             }
         }
 
-        // Load image content
-        let image_result = self.client.fetch_image(page, part);
-        match image_result {
+        match self.client.fetch_image(page, part) {
             Ok(img) => {
                 // Convert DynamicImage to Protocol using the picker
                 let protocol = self.picker.new_resize_protocol(img);
@@ -86,13 +109,48 @@ impl App {
             }
         }
 
+        if let Some(age) = self.client.take_staleness() {
+            self.message = Some(format!("Offline/stale data ({}s old)", age.as_secs()));
+        }
+
         self.current_page = page;
         self.current_part = part;
         self.loading = false;
+
+        // Any earlier prefetches were aimed at the old neighbourhood; cancel
+        // them before kicking off new ones for the page we just landed on.
+        self.client.cancel_pending_prefetches();
+        self.client.prefetch_neighbors(page, part);
+    }
+
+    /// Advances to the next sub-page for auto-rotate mode, or wraps back to
+    /// part 1 if there isn't one. The first time a page is rotated, its real
+    /// sub-page count isn't known yet, so we probe one page ahead and let a
+    /// not-found response teach us the boundary; every cycle after that we
+    /// wrap at the known max instead of probing past it again.
+    fn auto_advance_part(&mut self) {
+        let page = self.current_page;
+        match self.client.known_subpage_count(page) {
+            Some(1) => return,
+            Some(max) if self.current_part >= max => {
+                self.load_page(page, 1);
+                return;
+            }
+            _ => {}
+        }
+
+        let next_part = self.current_part + 1;
+        self.load_page(page, next_part);
+        if self.error.is_some() {
+            self.load_page(page, 1);
+        }
     }
 }
 
 fn main() -> Result<()> {
+    let offline = std::env::args().any(|arg| arg == "--offline");
+    let config = Config::load();
+
     // Create picker before entering raw mode to allow stdio queries
     let picker = Picker::from_query_stdio().unwrap_or_else(|_| {
         Picker::from_fontsize((8, 16))
@@ -104,8 +162,9 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new_with_picker(picker);
-    app.load_page(100, 1);
+    let mut app = App::new_with_picker(picker, config, offline);
+    let start_page = app.current_page;
+    app.load_page(start_page, 1);
 
     let res = run_app(&mut terminal, &mut app);
 
@@ -133,6 +192,30 @@ fn run_app<B: ratatui::backend::Backend>(
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
+                if let Some(buffer) = app.favorite_input_buffer.as_mut() {
+                    match key.code {
+                        KeyCode::Enter => {
+                            let name = buffer.clone();
+                            app.favorite_input_buffer = None;
+                            match app.config.favorites.get(&name).copied() {
+                                Some(page) => app.load_page(page, 1),
+                                None => app.message = Some(format!("No favorite named '{}'", name)),
+                            }
+                        }
+                        KeyCode::Esc => {
+                            app.favorite_input_buffer = None;
+                        }
+                        KeyCode::Backspace => {
+                            buffer.pop();
+                        }
+                        KeyCode::Char(c) if !c.is_control() => {
+                            buffer.push(c);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
                 match key.code {
                     KeyCode::Char('q') => return Ok(()),
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -149,6 +232,18 @@ fn run_app<B: ratatui::backend::Backend>(
                             DisplayMode::Image => DisplayMode::Text,
                         };
                     }
+                    KeyCode::Char('f') => {
+                        app.favorite_input_buffer = Some(String::new());
+                    }
+                    KeyCode::Char('r') => {
+                        app.auto_rotate = !app.auto_rotate;
+                        app.last_rotate = Instant::now();
+                        app.message = Some(if app.auto_rotate {
+                            "Auto-rotate on".to_string()
+                        } else {
+                            "Auto-rotate off".to_string()
+                        });
+                    }
                     KeyCode::Left => {
                         if app.current_page > 100 {
                             app.load_page(app.current_page - 1, 1);
@@ -192,6 +287,19 @@ fn run_app<B: ratatui::backend::Backend>(
                 }
             }
         }
+
+        // Completed background prefetches already landed in the shared
+        // caches; draining just keeps the notification channel from growing.
+        app.client.drain_prefetch_results();
+
+        if app.auto_rotate
+            && !app.loading
+            && app.page_input_buffer.is_empty()
+            && app.favorite_input_buffer.is_none()
+            && app.last_rotate.elapsed() >= AUTO_ROTATE_INTERVAL
+        {
+            app.auto_advance_part();
+        }
     }
 }
 
@@ -242,21 +350,29 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
                     // Add vertical padding lines at the top
                     let mut all_lines: Vec<Line> = vec![Line::from(""); vertical_padding];
 
-                    // Display the parsed content with horizontal centering
+                    // Display the parsed content with horizontal centering,
+                    // preserving each span's teletext foreground/background color
+                    let terminal_width = content_area.width as usize;
                     let content_lines: Vec<Line> = page_content
                         .lines
                         .iter()
-                        .map(|s| {
-                            // Center each line horizontally by adding padding
-                            let terminal_width = content_area.width as usize;
-                            let line_len = s.len();
+                        .map(|spans| {
+                            let line_len: usize = spans.iter().map(|s| s.text.len()).sum();
+                            let mut rendered: Vec<Span> = Vec::with_capacity(spans.len() + 1);
                             if line_len < terminal_width {
                                 let padding = (terminal_width - line_len) / 2;
-                                let padded = format!("{}{}", " ".repeat(padding), s);
-                                Line::from(padded)
-                            } else {
-                                Line::from(s.as_str())
+                                rendered.push(Span::styled(
+                                    " ".repeat(padding),
+                                    Style::default().bg(Color::Black),
+                                ));
+                            }
+                            for span in spans {
+                                rendered.push(Span::styled(
+                                    span.text.clone(),
+                                    Style::default().fg(span.fg).bg(span.bg),
+                                ));
                             }
+                            Line::from(rendered)
                         })
                         .collect();
 
@@ -297,10 +413,12 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
 
     let footer_text = if let Some(ref msg) = app.message {
         format!("  {}", msg)
+    } else if let Some(ref buffer) = app.favorite_input_buffer {
+        format!("  Go to favorite: {}_", buffer)
     } else if !app.page_input_buffer.is_empty() {
         format!("  Go to page: {}_", app.page_input_buffer)
     } else {
-        "  [← / →] Page  [↑/↓] Sub-page  [0-9] Jump  [v] Toggle view  [q] Quit  [c] Clear cache".to_string()
+        "  [← / →] Page  [↑/↓] Sub-page  [0-9] Jump  [f] Favorite  [r] Auto-rotate  [v] Toggle view  [q] Quit  [c] Clear cache".to_string()
     };
 
     let footer_line = create_bar(&footer_text, "", size.width);